@@ -6,7 +6,7 @@ use std::vec::Vec;
 use crate::dataflow::{ThrillerEdge, ThrillerNode, ThrillerNodeInner};
 use crate::task::Task;
 use crate::{debug, AttachedEdge};
-use crate::{next_id, MemoryLevel, ThrillerResult};
+use crate::{next_id, MemoryLevel, ThrillerError, ThrillerResult};
 
 use super::loop_analysis::LoopGroup;
 
@@ -21,6 +21,54 @@ pub struct ThrillerGraph {
     mem_level: MemoryLevel,
 }
 
+/// Pick a stable Graphviz fill color for a `mem_level`, so `to_dot`
+/// clusters from the same memory level always render the same color.
+fn mem_level_color(mem_level: &MemoryLevel) -> &'static str {
+    const PALETTE: &[&str] = &["lightblue", "lightyellow", "lightgreen", "lightpink", "lightgrey"];
+    let hash = format!("{:?}", mem_level)
+        .bytes()
+        .fold(0usize, |acc, byte| acc.wrapping_mul(31).wrapping_add(byte as usize));
+    PALETTE[hash % PALETTE.len()]
+}
+
+/// Undo the adjacency a previous `connect()` established for a single
+/// edge, the inverse of `add_out_edge`/`add_next`/`add_in_edge`/
+/// `add_prev`/`inc_in_degrees`. Self-loops take one borrow, same as
+/// `connect()`, since `src` and `dst` are the same node.
+fn disconnect_edge(edge: &Rc<ThrillerEdge>) {
+    let src = edge.get_src();
+    let dst = edge.get_dst();
+
+    if Rc::ptr_eq(&src, &dst) {
+        let mut node_ref = src.borrow_mut();
+        node_ref.remove_out_edge(edge.clone());
+        node_ref.remove_in_edge(edge.clone());
+        node_ref.remove_next(&dst);
+        node_ref.remove_prev(&src);
+        node_ref.dec_in_degrees();
+        return;
+    }
+
+    let mut src_ref = src.borrow_mut();
+    let mut dst_ref = dst.borrow_mut();
+
+    src_ref.remove_out_edge(edge.clone());
+    dst_ref.remove_in_edge(edge.clone());
+
+    src_ref.remove_next(&dst);
+    dst_ref.remove_prev(&src);
+
+    dst_ref.dec_in_degrees();
+}
+
+/// Clear the `nexts`/`prevs`/`in_degree`/`out_edges`/`in_edges` a prior
+/// `connect()` left on a node, so it can be folded into a fresh
+/// subgraph's own `connect()` pass without carrying stale adjacency
+/// from whatever graph it used to belong to.
+fn reset_adjacency(node: &Rc<RefCell<ThrillerNode>>) {
+    node.borrow_mut().clear_adjacency();
+}
+
 impl ThrillerGraph {
     /// Create a new empty ThrillerGraph.
     pub fn new(mem_level: MemoryLevel) -> Self {
@@ -48,6 +96,19 @@ impl ThrillerGraph {
             let src = edge.get_src();
             let dst = edge.get_dst();
 
+            // A self-loop edge has `src` and `dst` pointing at the same
+            // `Rc<RefCell<_>>`, so a second `borrow_mut()` would panic;
+            // take one borrow and apply both sides of the update to it.
+            if Rc::ptr_eq(&src, &dst) {
+                let mut node_ref = src.borrow_mut();
+                node_ref.add_out_edge(edge.clone());
+                node_ref.add_in_edge(edge.clone());
+                node_ref.add_next(dst.clone());
+                node_ref.add_prev(src.clone());
+                node_ref.inc_in_degrees();
+                continue;
+            }
+
             let mut src_ref = src.borrow_mut();
             let mut dst_ref = dst.borrow_mut();
 
@@ -62,7 +123,13 @@ impl ThrillerGraph {
     }
 
     /// Topological sort the nodes in the graph.
-    pub fn topo_sort(&self) -> Vec<Rc<RefCell<ThrillerNode>>> {
+    ///
+    /// Returns an error if the graph contains a cycle: after a full pass
+    /// over the remaining nodes finds none with in-degree 0, the nodes
+    /// still stuck with a nonzero in-degree (the nodes on a cycle plus
+    /// any node downstream of one) have their ids reported via
+    /// [`ThrillerError::CyclicGraph`] instead of spinning forever.
+    pub fn topo_sort(&self) -> ThrillerResult<Vec<Rc<RefCell<ThrillerNode>>>> {
         let mut sorted_nodes = Vec::new();
         // (id, (in_degrees, node))
         let mut in_degrees: HashMap<usize, (usize, &Rc<RefCell<ThrillerNode>>)> = HashMap::new();
@@ -79,9 +146,12 @@ impl ThrillerGraph {
 
         while !in_degrees.is_empty() {
             let node_ids = in_degrees.keys().cloned().collect::<Vec<_>>();
+            let mut made_progress = false;
+
             for node_id in node_ids {
                 let (in_degree, node) = in_degrees[&node_id];
                 if in_degree == 0 {
+                    made_progress = true;
                     sorted_nodes.push(node.clone());
 
                     for next in node.borrow_mut().get_nexts() {
@@ -93,14 +163,103 @@ impl ThrillerGraph {
                     in_degrees.remove(&node_id);
                 }
             }
+
+            if !made_progress {
+                let cyclic_ids = in_degrees.keys().cloned().collect::<Vec<_>>();
+                return Err(ThrillerError::CyclicGraph(cyclic_ids));
+            }
+        }
+
+        Ok(sorted_nodes)
+    }
+
+    /// Find the strongly-connected components of the graph, via an
+    /// iterative Tarjan's algorithm so deep graphs can't overflow the
+    /// stack. A component of size greater than one, or a singleton with
+    /// a self-edge, marks a cyclic region.
+    pub fn find_sccs(&self) -> Vec<Vec<Rc<RefCell<ThrillerNode>>>> {
+        let mut index_map: HashMap<usize, usize> = HashMap::new();
+        let mut lowlink_map: HashMap<usize, usize> = HashMap::new();
+        let mut on_stack: HashMap<usize, bool> = HashMap::new();
+        let mut counter = 0usize;
+        let mut component_stack: Vec<Rc<RefCell<ThrillerNode>>> = Vec::new();
+        let mut sccs = Vec::new();
+
+        // Explicit DFS stack: each frame is (node, index of its next
+        // successor to visit), standing in for a recursive call frame.
+        let mut work_stack: Vec<(Rc<RefCell<ThrillerNode>>, usize)> = Vec::new();
+
+        for node in &self.nodes {
+            let start_id = node.borrow().get_id();
+            if index_map.contains_key(&start_id) {
+                continue;
+            }
+
+            work_stack.push((node.clone(), 0));
+
+            while let Some((current, next_succ)) = work_stack.pop() {
+                let current_id = current.borrow().get_id();
+
+                if next_succ == 0 {
+                    index_map.insert(current_id, counter);
+                    lowlink_map.insert(current_id, counter);
+                    counter += 1;
+                    component_stack.push(current.clone());
+                    on_stack.insert(current_id, true);
+                }
+
+                let nexts = current.borrow().get_nexts();
+                if next_succ < nexts.len() {
+                    let succ = nexts[next_succ].clone();
+                    let succ_id = succ.borrow().get_id();
+
+                    // Resume this frame at the following successor once
+                    // the child visit below (if any) completes.
+                    work_stack.push((current.clone(), next_succ + 1));
+
+                    if !index_map.contains_key(&succ_id) {
+                        work_stack.push((succ.clone(), 0));
+                    } else if *on_stack.get(&succ_id).unwrap_or(&false) {
+                        let succ_index = index_map[&succ_id];
+                        let current_low = lowlink_map[&current_id];
+                        lowlink_map.insert(current_id, current_low.min(succ_index));
+                    }
+                    continue;
+                }
+
+                // All successors visited: propagate the lowlink up to the
+                // parent frame, then pop a component if this node roots one.
+                if let Some((parent, _)) = work_stack.last() {
+                    let parent_id = parent.borrow().get_id();
+                    if index_map.contains_key(&parent_id) {
+                        let current_low = lowlink_map[&current_id];
+                        let parent_low = lowlink_map[&parent_id];
+                        lowlink_map.insert(parent_id, parent_low.min(current_low));
+                    }
+                }
+
+                if lowlink_map[&current_id] == index_map[&current_id] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = component_stack.pop().unwrap();
+                        let member_id = member.borrow().get_id();
+                        on_stack.insert(member_id, false);
+                        component.push(member.clone());
+                        if member_id == current_id {
+                            break;
+                        }
+                    }
+                    sccs.push(component);
+                }
+            }
         }
 
-        sorted_nodes
+        sccs
     }
 
     /// Reduce the block outputs in the graph.
-    pub fn reduce_block_outputs(&self) -> Option<Vec<Rc<AttachedEdge>>> {
-        let sorted_nodes = self.topo_sort();
+    pub fn reduce_block_outputs(&self) -> ThrillerResult<Option<Vec<Rc<AttachedEdge>>>> {
+        let sorted_nodes = self.topo_sort()?;
 
         for node in sorted_nodes {
             if let ThrillerNodeInner::Block(block) = node.borrow().get_inner() {
@@ -110,38 +269,488 @@ impl ThrillerGraph {
                     for output in outputs {
                         reduced_outputs.push(output.clone());
                     }
-                    return Some(reduced_outputs);
+                    return Ok(Some(reduced_outputs));
                 }
-                return None;
+                return Ok(None);
             }
         }
 
-        None
+        Ok(None)
+    }
+
+    /// Compute a structural diff against another version of this graph,
+    /// e.g. before/after an optimization pass, for use as a pass
+    /// regression check. Matches nodes greedily by kind and topological
+    /// position via a Levenshtein-style alignment, so a single inserted
+    /// or deleted node doesn't churn the rest of the edit script.
+    pub fn diff(&self, other: &ThrillerGraph) -> GraphDiff {
+        let self_sorted = self.topo_sort().unwrap_or_else(|_| self.nodes.clone());
+        let other_sorted = other.topo_sort().unwrap_or_else(|_| other.nodes.clone());
+
+        let kind_of = |node: &Rc<RefCell<ThrillerNode>>| -> &'static str {
+            match node.borrow().get_inner() {
+                ThrillerNodeInner::Op(_) => "op",
+                ThrillerNodeInner::Block(_) => "block",
+                ThrillerNodeInner::Buffer(_) => "buffer",
+            }
+        };
+
+        // Predecessor and successor ids per graph, keyed by node id and
+        // read from that graph's own edges, so `edge_sig` can compare
+        // actual neighbor identity rather than just degree counts (two
+        // differently-wired nodes can share a degree count), and a node
+        // shared by both graphs is compared by each graph's own wiring.
+        fn neighbor_ids(graph: &ThrillerGraph, outgoing: bool) -> HashMap<usize, Vec<usize>> {
+            let mut neighbors: HashMap<usize, Vec<usize>> = HashMap::new();
+            for edge in &graph.edges {
+                let src_id = edge.get_src().borrow().get_id();
+                let dst_id = edge.get_dst().borrow().get_id();
+                let (key, neighbor) = if outgoing { (src_id, dst_id) } else { (dst_id, src_id) };
+                neighbors.entry(key).or_default().push(neighbor);
+            }
+            for ids in neighbors.values_mut() {
+                ids.sort_unstable();
+            }
+            neighbors
+        }
+
+        let self_preds = neighbor_ids(self, false);
+        let self_succs = neighbor_ids(self, true);
+        let other_preds = neighbor_ids(other, false);
+        let other_succs = neighbor_ids(other, true);
+
+        let edge_sig = |preds: &HashMap<usize, Vec<usize>>,
+                         succs: &HashMap<usize, Vec<usize>>,
+                         node: &Rc<RefCell<ThrillerNode>>|
+         -> (Vec<usize>, Vec<usize>) {
+            let id = node.borrow().get_id();
+            let pred_ids = preds.get(&id).cloned().unwrap_or_default();
+            let succ_ids = succs.get(&id).cloned().unwrap_or_default();
+            (pred_ids, succ_ids)
+        };
+
+        let n = self_sorted.len();
+        let m = other_sorted.len();
+
+        // dp[i][j] = cost of aligning self_sorted[..i] with other_sorted[..j].
+        let mut dp = vec![vec![0usize; m + 1]; n + 1];
+        for (i, row) in dp.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        for (j, cell) in dp[0].iter_mut().enumerate() {
+            *cell = j;
+        }
+
+        for i in 1..=n {
+            for j in 1..=m {
+                let substitute_cost = if kind_of(&self_sorted[i - 1]) == kind_of(&other_sorted[j - 1])
+                {
+                    0
+                } else {
+                    1
+                };
+                dp[i][j] = (dp[i - 1][j - 1] + substitute_cost)
+                    .min(dp[i - 1][j] + 1)
+                    .min(dp[i][j - 1] + 1);
+            }
+        }
+
+        // Backtrack through the DP table to produce the edit script.
+        let mut entries = Vec::new();
+        let mut i = n;
+        let mut j = m;
+        while i > 0 || j > 0 {
+            let diag_cost = if i > 0 && j > 0 {
+                let substitute_cost = if kind_of(&self_sorted[i - 1]) == kind_of(&other_sorted[j - 1])
+                {
+                    0
+                } else {
+                    1
+                };
+                Some(dp[i - 1][j - 1] + substitute_cost)
+            } else {
+                None
+            };
+
+            if i > 0 && j > 0 && diag_cost == Some(dp[i][j]) {
+                let before = self_sorted[i - 1].clone();
+                let after = other_sorted[j - 1].clone();
+                if kind_of(&before) == kind_of(&after)
+                    && edge_sig(&self_preds, &self_succs, &before)
+                        == edge_sig(&other_preds, &other_succs, &after)
+                {
+                    entries.push(NodeDiff::Unchanged(after));
+                } else {
+                    entries.push(NodeDiff::Changed { before, after });
+                }
+                i -= 1;
+                j -= 1;
+            } else if i > 0 && dp[i][j] == dp[i - 1][j] + 1 {
+                entries.push(NodeDiff::Deleted(self_sorted[i - 1].clone()));
+                i -= 1;
+            } else {
+                entries.push(NodeDiff::Added(other_sorted[j - 1].clone()));
+                j -= 1;
+            }
+        }
+
+        entries.reverse();
+
+        // Union of both graphs' edges, so a rendered diff stays
+        // connected even for nodes that only exist on one side.
+        let mut seen_edges = std::collections::HashSet::new();
+        let mut edges = Vec::new();
+        for edge in self.edges.iter().chain(other.edges.iter()) {
+            let key = (
+                edge.get_src().borrow().get_id(),
+                edge.get_dst().borrow().get_id(),
+            );
+            if seen_edges.insert(key) {
+                edges.push(edge.clone());
+            }
+        }
+
+        GraphDiff { entries, edges }
+    }
+
+    /// Compute a greedy feedback arc set, remove it from the graph, and
+    /// return it, so the remainder is a DAG `topo_sort` can order. Builds
+    /// a sequence by peeling sinks/sources and then placing the node
+    /// maximizing (out-degree − in-degree); an edge pointing backwards in
+    /// that sequence is a feedback arc. Self-loops are always feedback.
+    /// Removed edges are also unwound from the node-level adjacency
+    /// `connect()` built, not just dropped from `self.edges`.
+    pub fn break_cycles(&mut self) -> Vec<Rc<ThrillerEdge>> {
+        let mut succs: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut preds: HashMap<usize, Vec<usize>> = HashMap::new();
+
+        for node in &self.nodes {
+            let id = node.borrow().get_id();
+            succs.entry(id).or_default();
+            preds.entry(id).or_default();
+        }
+
+        let mut self_loops = Vec::new();
+        let mut ordering_edges = Vec::new();
+        for edge in &self.edges {
+            let src_id = edge.get_src().borrow().get_id();
+            let dst_id = edge.get_dst().borrow().get_id();
+            if src_id == dst_id {
+                self_loops.push(edge.clone());
+                continue;
+            }
+            succs.entry(src_id).or_default().push(dst_id);
+            preds.entry(dst_id).or_default().push(src_id);
+            ordering_edges.push(edge.clone());
+        }
+
+        let mut remaining: std::collections::HashSet<usize> = succs.keys().cloned().collect();
+        // Sources and picked nodes grow `head` left to right; sinks grow
+        // `tail` right to left, so each peeled sink lands before the sinks
+        // peeled earlier. The final sequence is `head` then `tail`.
+        let mut head: Vec<usize> = Vec::new();
+        let mut tail: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+
+        while !remaining.is_empty() {
+            let mut progressed = true;
+            while progressed {
+                progressed = false;
+
+                let sinks: Vec<usize> = remaining
+                    .iter()
+                    .filter(|id| succs[*id].iter().all(|n| !remaining.contains(n)))
+                    .cloned()
+                    .collect();
+                for id in sinks {
+                    remaining.remove(&id);
+                    tail.push_front(id);
+                    progressed = true;
+                }
+
+                let sources: Vec<usize> = remaining
+                    .iter()
+                    .filter(|id| preds[*id].iter().all(|n| !remaining.contains(n)))
+                    .cloned()
+                    .collect();
+                for id in sources {
+                    remaining.remove(&id);
+                    head.push(id);
+                    progressed = true;
+                }
+            }
+
+            if remaining.is_empty() {
+                break;
+            }
+
+            let best = *remaining
+                .iter()
+                .max_by_key(|id| {
+                    let out_degree =
+                        succs[*id].iter().filter(|n| remaining.contains(n)).count() as isize;
+                    let in_degree =
+                        preds[*id].iter().filter(|n| remaining.contains(n)).count() as isize;
+                    out_degree - in_degree
+                })
+                .expect("remaining is non-empty");
+
+            remaining.remove(&best);
+            head.push(best);
+        }
+
+        let position: HashMap<usize, usize> = head
+            .iter()
+            .chain(tail.iter())
+            .enumerate()
+            .map(|(pos, id)| (*id, pos))
+            .collect();
+
+        let (mut feedback, kept): (Vec<_>, Vec<_>) = ordering_edges.into_iter().partition(|edge| {
+            let src_pos = position[&edge.get_src().borrow().get_id()];
+            let dst_pos = position[&edge.get_dst().borrow().get_id()];
+            src_pos > dst_pos
+        });
+
+        feedback.extend(self_loops);
+        for edge in &feedback {
+            disconnect_edge(edge);
+        }
+        self.edges = kept;
+        feedback
+    }
+
+    /// Render this graph as Graphviz DOT, independent of the `Task` emit
+    /// pipeline, so it can be dumped for debugging at any point in
+    /// lowering. Nodes are labeled by their `ThrillerNodeInner` variant
+    /// and id, grouped into a `subgraph cluster_*` colored and labeled by
+    /// `mem_level`, and edges carry the attached access info when an
+    /// edge has one.
+    ///
+    /// `mem_level` lives on the whole `ThrillerGraph`, not per node, so a
+    /// single call only ever produces one cluster; the nested memory
+    /// hierarchy becomes visible by composing the DOT output of several
+    /// graphs, e.g. the children returned by `try_split` or
+    /// `try_find_disconnected_subgraph`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph ThrillerGraph {\n");
+
+        let cluster_name = format!("cluster_{:?}", self.mem_level)
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+            .collect::<String>();
+        dot.push_str(&format!("  subgraph {} {{\n", cluster_name));
+        dot.push_str(&format!("    label = \"{:?}\";\n", self.mem_level));
+        dot.push_str("    style = filled;\n");
+        dot.push_str(&format!(
+            "    color = {};\n",
+            mem_level_color(&self.mem_level)
+        ));
+
+        for node in &self.nodes {
+            let node_ref = node.borrow();
+            let id = node_ref.get_id();
+            let (shape, label) = match node_ref.get_inner() {
+                ThrillerNodeInner::Op(_) => ("box", format!("Op{}", id)),
+                ThrillerNodeInner::Block(_) => ("ellipse", format!("Block{}", id)),
+                ThrillerNodeInner::Buffer(_) => ("cylinder", format!("Buffer{}", id)),
+            };
+            dot.push_str(&format!(
+                "    n{} [label=\"{}\", shape={}];\n",
+                id, label, shape
+            ));
+        }
+
+        dot.push_str("  }\n");
+
+        for edge in &self.edges {
+            let src_id = edge.get_src().borrow().get_id();
+            let dst_id = edge.get_dst().borrow().get_id();
+            let label = edge
+                .get_attached_edge()
+                .map(|attached| format!(" [label=\"{}\"]", attached))
+                .unwrap_or_default();
+            dot.push_str(&format!("  n{} -> n{}{};\n", src_id, dst_id, label));
+        }
+
+        dot.push_str("}\n");
+        dot
     }
 }
 
 impl ThrillerGraph {
     /// Try to find the disconnected subgraph in the graph.
+    ///
+    /// Partitions the nodes into weakly-connected components with a
+    /// union-find over node ids, then builds one fresh `ThrillerGraph`
+    /// per component, preserving `mem_level` and re-running `connect()`.
+    /// Returns `None` when the graph is already a single component. The
+    /// nodes are shared `Rc`s from `self`, so on a `Some` return this
+    /// resets `self`'s own node adjacency before handing it to the
+    /// children — hence `&mut self` rather than `&self`: otherwise
+    /// re-running `connect()` per child would double the adjacency onto
+    /// the same node objects instead.
     #[allow(dead_code)]
-    pub(crate) fn try_find_disconnected_subgraph(&self) -> Option<Vec<ThrillerGraph>> {
-        todo!("find_disconnected_subgraph not implemented yet");
+    pub(crate) fn try_find_disconnected_subgraph(&mut self) -> Option<Vec<ThrillerGraph>> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut parent: HashMap<usize, usize> = HashMap::new();
+        for node in &self.nodes {
+            let id = node.borrow().get_id();
+            parent.insert(id, id);
+        }
+
+        fn find(parent: &mut HashMap<usize, usize>, id: usize) -> usize {
+            let root = parent[&id];
+            if root != id {
+                let found = find(parent, root);
+                parent.insert(id, found);
+                found
+            } else {
+                id
+            }
+        }
+
+        fn union(parent: &mut HashMap<usize, usize>, a: usize, b: usize) {
+            let ra = find(parent, a);
+            let rb = find(parent, b);
+            if ra != rb {
+                parent.insert(ra, rb);
+            }
+        }
+
+        for edge in &self.edges {
+            let src_id = edge.get_src().borrow().get_id();
+            let dst_id = edge.get_dst().borrow().get_id();
+            union(&mut parent, src_id, dst_id);
+        }
+
+        let mut buckets: HashMap<usize, Vec<Rc<RefCell<ThrillerNode>>>> = HashMap::new();
+        for node in &self.nodes {
+            let id = node.borrow().get_id();
+            let root = find(&mut parent, id);
+            buckets.entry(root).or_default().push(node.clone());
+        }
+
+        if buckets.len() <= 1 {
+            return None;
+        }
+
+        for node in &self.nodes {
+            reset_adjacency(node);
+        }
+
+        let mut subgraphs = Vec::new();
+        for bucket_nodes in buckets.into_values() {
+            let bucket_ids: std::collections::HashSet<usize> =
+                bucket_nodes.iter().map(|n| n.borrow().get_id()).collect();
+
+            let bucket_edges = self
+                .edges
+                .iter()
+                .filter(|edge| {
+                    let src_id = edge.get_src().borrow().get_id();
+                    let dst_id = edge.get_dst().borrow().get_id();
+                    bucket_ids.contains(&src_id) && bucket_ids.contains(&dst_id)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let mut subgraph = ThrillerGraph::new(self.mem_level.clone());
+            subgraph.add_nodes(bucket_nodes);
+            subgraph.add_edges(bucket_edges);
+            subgraph.connect();
+            subgraphs.push(subgraph);
+        }
+
+        Some(subgraphs)
     }
 
     /// Try to split graph based on various loop nests.
+    ///
+    /// Assigns each node to the `LoopGroup` that dominates it and builds
+    /// one child `ThrillerGraph` per group. A boundary-crossing edge
+    /// can't go through either child's own `connect()` without
+    /// corrupting the in-degree of a node that child doesn't have, so
+    /// those are returned separately instead. A node outside every group
+    /// is dropped, along with any edge touching it. The nodes are shared
+    /// `Rc`s from `self`, so their adjacency from `self`'s own
+    /// `connect()` is reset before the children rebuild it, or a
+    /// child's `topo_sort` would walk a `nexts` entry pointing outside
+    /// its own node set.
     #[allow(dead_code)]
-    pub(crate) fn try_split(&mut self, groups: &[LoopGroup]) -> Option<Vec<ThrillerGraph>> {
+    pub(crate) fn try_split(
+        &mut self,
+        groups: &[LoopGroup],
+    ) -> Option<(Vec<ThrillerGraph>, Vec<Rc<ThrillerEdge>>)> {
         if groups.len() == 1 {
             return None;
         }
 
-        todo!("try_split not implemented yet");
+        // Map each node id to the index of the loop group that dominates it.
+        let mut node_group: HashMap<usize, usize> = HashMap::new();
+        for node in &self.nodes {
+            let id = node.borrow().get_id();
+            for (group_idx, group) in groups.iter().enumerate() {
+                if group.contains_node(id) {
+                    node_group.insert(id, group_idx);
+                    break;
+                }
+            }
+        }
+
+        for node in &self.nodes {
+            reset_adjacency(node);
+        }
+
+        let mut subgraphs: Vec<ThrillerGraph> = groups
+            .iter()
+            .map(|_| ThrillerGraph::new(self.mem_level.clone()))
+            .collect();
+
+        for node in &self.nodes {
+            let id = node.borrow().get_id();
+            if let Some(&group_idx) = node_group.get(&id) {
+                subgraphs[group_idx].add_nodes(vec![node.clone()]);
+            }
+        }
+
+        let mut boundary_edges = Vec::new();
+
+        for edge in &self.edges {
+            let src_id = edge.get_src().borrow().get_id();
+            let dst_id = edge.get_dst().borrow().get_id();
+
+            match (node_group.get(&src_id), node_group.get(&dst_id)) {
+                (Some(&sg), Some(&dg)) if sg == dg => {
+                    subgraphs[sg].add_edges(vec![edge.clone()]);
+                }
+                (Some(_), Some(_)) => {
+                    boundary_edges.push(edge.clone());
+                }
+                _ => {
+                    debug!(
+                        "edge {} -> {} has an endpoint outside every LoopGroup; dropping from split",
+                        src_id, dst_id
+                    );
+                }
+            }
+        }
+
+        for subgraph in &mut subgraphs {
+            subgraph.connect();
+        }
+
+        Some((subgraphs, boundary_edges))
     }
 }
 
 impl Task for ThrillerGraph {
     fn emit(&self) -> ThrillerResult<String> {
         let mut code = String::new();
-        let sorted_nodes = self.topo_sort();
+        let sorted_nodes = self.topo_sort()?;
 
         for node in sorted_nodes {
             match node.borrow().get_inner() {
@@ -162,3 +771,199 @@ impl Task for ThrillerGraph {
         todo!()
     }
 }
+
+/// One entry in the edit script produced by [`ThrillerGraph::diff`].
+pub enum NodeDiff {
+    /// Present in both graphs with the same kind and incident edges.
+    Unchanged(Rc<RefCell<ThrillerNode>>),
+    /// Present only in the graph passed as `other`.
+    Added(Rc<RefCell<ThrillerNode>>),
+    /// Present only in `self`.
+    Deleted(Rc<RefCell<ThrillerNode>>),
+    /// Matched by topological position but differing in kind or edges.
+    Changed {
+        before: Rc<RefCell<ThrillerNode>>,
+        after: Rc<RefCell<ThrillerNode>>,
+    },
+}
+
+/// Structural diff between two versions of a `ThrillerGraph`, as produced
+/// by [`ThrillerGraph::diff`], giving pass authors a regression check
+/// between the IR before and after an optimization pass.
+pub struct GraphDiff {
+    pub entries: Vec<NodeDiff>,
+    /// Union of both graphs' edges, carried along so `to_dot` can render
+    /// a connected picture rather than just isolated colored nodes.
+    pub edges: Vec<Rc<ThrillerEdge>>,
+}
+
+impl GraphDiff {
+    /// Render this diff as Graphviz DOT, coloring added, deleted, and
+    /// changed nodes distinctly from unchanged ones.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph ThrillerGraphDiff {\n");
+
+        for entry in &self.entries {
+            let (id, color) = match entry {
+                NodeDiff::Unchanged(node) => (node.borrow().get_id(), "black"),
+                NodeDiff::Added(node) => (node.borrow().get_id(), "green"),
+                NodeDiff::Deleted(node) => (node.borrow().get_id(), "red"),
+                NodeDiff::Changed { after, .. } => (after.borrow().get_id(), "orange"),
+            };
+            dot.push_str(&format!(
+                "  n{} [label=\"n{}\", color={}];\n",
+                id, id, color
+            ));
+        }
+
+        for edge in &self.edges {
+            let src_id = edge.get_src().borrow().get_id();
+            let dst_id = edge.get_dst().borrow().get_id();
+            dot.push_str(&format!("  n{} -> n{};\n", src_id, dst_id));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_node() -> Rc<RefCell<ThrillerNode>> {
+        Rc::new(RefCell::new(ThrillerNode::new(ThrillerNodeInner::Buffer(
+            Default::default(),
+        ))))
+    }
+
+    fn edge(src: &Rc<RefCell<ThrillerNode>>, dst: &Rc<RefCell<ThrillerNode>>) -> Rc<ThrillerEdge> {
+        Rc::new(ThrillerEdge::new(src.clone(), dst.clone()))
+    }
+
+    #[test]
+    fn topo_sort_reports_cyclic_graph() {
+        let a = buffer_node();
+        let b = buffer_node();
+
+        let mut graph = ThrillerGraph::new(MemoryLevel::default());
+        graph.add_nodes(vec![a.clone(), b.clone()]);
+        graph.add_edges(vec![edge(&a, &b), edge(&b, &a)]);
+        graph.connect();
+
+        let mut cyclic_ids = match graph.topo_sort() {
+            Err(ThrillerError::CyclicGraph(ids)) => ids,
+            other => panic!("expected CyclicGraph error, got {:?}", other.is_ok()),
+        };
+        cyclic_ids.sort_unstable();
+
+        let mut expected = vec![a.borrow().get_id(), b.borrow().get_id()];
+        expected.sort_unstable();
+        assert_eq!(cyclic_ids, expected);
+    }
+
+    #[test]
+    fn find_sccs_groups_a_cycle_and_leaves_acyclic_nodes_singleton() {
+        let a = buffer_node();
+        let b = buffer_node();
+        let c = buffer_node();
+
+        let mut graph = ThrillerGraph::new(MemoryLevel::default());
+        graph.add_nodes(vec![a.clone(), b.clone(), c.clone()]);
+        graph.add_edges(vec![edge(&a, &b), edge(&b, &a), edge(&a, &c)]);
+        graph.connect();
+
+        let sccs = graph.find_sccs();
+        let sizes: Vec<usize> = sccs.iter().map(|scc| scc.len()).collect();
+        assert!(sizes.contains(&2), "expected the a<->b cycle as one SCC");
+        assert!(sizes.contains(&1), "expected c as its own SCC");
+    }
+
+    #[test]
+    fn try_find_disconnected_subgraph_splits_weakly_connected_components() {
+        let a = buffer_node();
+        let b = buffer_node();
+        let c = buffer_node();
+        let d = buffer_node();
+
+        let mut graph = ThrillerGraph::new(MemoryLevel::default());
+        graph.add_nodes(vec![a.clone(), b.clone(), c.clone(), d.clone()]);
+        graph.add_edges(vec![edge(&a, &b), edge(&c, &d)]);
+        graph.connect();
+
+        let subgraphs = graph.try_find_disconnected_subgraph().unwrap();
+        assert_eq!(subgraphs.len(), 2);
+        assert!(subgraphs.iter().all(|g| g.topo_sort().is_ok()));
+    }
+
+    #[test]
+    fn try_split_reports_boundary_edges_without_duplicating_them() {
+        let a = buffer_node();
+        let b = buffer_node();
+        let c = buffer_node();
+
+        let mut graph = ThrillerGraph::new(MemoryLevel::default());
+        graph.add_nodes(vec![a.clone(), b.clone(), c.clone()]);
+        graph.add_edges(vec![edge(&a, &b), edge(&b, &c)]);
+        graph.connect();
+
+        let group0 = LoopGroup::new(vec![a.borrow().get_id(), b.borrow().get_id()]);
+        let group1 = LoopGroup::new(vec![c.borrow().get_id()]);
+
+        let (subgraphs, boundary_edges) = graph.try_split(&[group0, group1]).unwrap();
+        assert_eq!(subgraphs.len(), 2);
+        assert_eq!(boundary_edges.len(), 1, "b -> c should cross the boundary");
+        assert!(
+            subgraphs.iter().all(|g| g.topo_sort().is_ok()),
+            "neither child's connect() pass should have seen the boundary edge"
+        );
+    }
+
+    #[test]
+    fn break_cycles_always_removes_self_loops() {
+        let a = buffer_node();
+
+        let mut graph = ThrillerGraph::new(MemoryLevel::default());
+        graph.add_nodes(vec![a.clone()]);
+        graph.add_edges(vec![edge(&a, &a)]);
+        graph.connect();
+
+        let feedback = graph.break_cycles();
+        assert_eq!(feedback.len(), 1);
+        assert!(graph.topo_sort().is_ok());
+    }
+
+    #[test]
+    fn diff_reports_added_node_and_rewired_node_as_changed() {
+        let a = buffer_node();
+        let b = buffer_node();
+
+        let mut before = ThrillerGraph::new(MemoryLevel::default());
+        before.add_nodes(vec![a.clone(), b.clone()]);
+        before.connect();
+
+        let c = buffer_node();
+        let mut after = ThrillerGraph::new(MemoryLevel::default());
+        after.add_nodes(vec![a.clone(), b.clone(), c.clone()]);
+        after.add_edges(vec![edge(&a, &b)]);
+        after.connect();
+
+        let diff = before.diff(&after);
+        let added = diff
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry, NodeDiff::Added(_)))
+            .count();
+        assert_eq!(added, 1, "after has one more node, so exactly one entry should be Added");
+
+        let changed = diff
+            .entries
+            .iter()
+            .any(|entry| matches!(entry, NodeDiff::Changed { .. }));
+        assert!(
+            changed,
+            "a gained a successor, so it can't be reported Unchanged"
+        );
+    }
+}
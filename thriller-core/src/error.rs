@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// Errors produced while building, scheduling, or lowering a Thriller
+/// dataflow graph.
+#[derive(Debug)]
+pub enum ThrillerError {
+    /// The graph has no topological order: these are the ids of the
+    /// nodes still stuck with a nonzero in-degree once `topo_sort`
+    /// could make no further progress.
+    CyclicGraph(Vec<usize>),
+}
+
+impl fmt::Display for ThrillerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThrillerError::CyclicGraph(ids) => {
+                write!(f, "graph contains a cycle among nodes: {:?}", ids)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThrillerError {}
+
+/// Convenience alias for fallible Thriller operations.
+pub type ThrillerResult<T> = Result<T, ThrillerError>;